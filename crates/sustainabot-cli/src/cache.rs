@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// SPDX-FileCopyrightText: 2025 Jonathan D.A. Jewell
+
+//! Incremental, content-hashed analysis cache.
+//!
+//! Keyed by absolute path + content hash + analyzer version, this lets `check`
+//! skip re-analyzing files whose contents have not changed — the same
+//! incremental-recomputation idea rust-analyzer uses to keep whole-workspace
+//! analysis cheap. The cache is a single serde index plus one entry file per
+//! distinct content hash.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sustainabot_metrics::AnalysisResult;
+use tracing::info;
+
+/// Analyzer version the cache is valid for; a bump invalidates every entry.
+const ANALYZER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The cache round-trips [`AnalysisResult`] through serde, so the metrics crate
+/// must derive `Deserialize` on it — not just `Serialize`, which is all the
+/// baseline `to_string_pretty` output path exercised. Asserting the bound here
+/// makes a missing derive fail at this named boundary instead of deep inside a
+/// `serde_json::from_slice` call in [`AnalysisCache::read_entry`].
+const _: fn() = || {
+    fn assert_deserialize<T: serde::de::DeserializeOwned>() {}
+    assert_deserialize::<AnalysisResult>();
+};
+
+/// Default directory name for the on-disk cache.
+const CACHE_DIR_NAME: &str = ".sustainabot-cache";
+
+/// Persisted cache index mapping absolute paths to their last-seen content.
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+    /// Analyzer version that produced these entries.
+    analyzer_version: String,
+    /// Absolute file path -> content hash of the last analyzed contents.
+    entries: HashMap<String, u64>,
+}
+
+/// A content-hashed store of per-file [`AnalysisResult`] vectors.
+pub struct AnalysisCache {
+    dir: PathBuf,
+    index: CacheIndex,
+    hits: u32,
+    misses: u32,
+}
+
+impl AnalysisCache {
+    /// Open (or create) the cache in the conventional directory.
+    ///
+    /// A version mismatch clears the cache so stale results are never served.
+    pub fn open() -> Result<Self> {
+        Self::open_in(PathBuf::from(CACHE_DIR_NAME))
+    }
+
+    /// Open the cache rooted at `dir`.
+    pub fn open_in(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let index = Self::load_index(&dir);
+        Ok(Self { dir, index, hits: 0, misses: 0 })
+    }
+
+    /// Remove every cached entry and the index.
+    pub fn clean(dir: &Path) -> Result<()> {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+
+    /// The conventional cache directory.
+    pub fn dir() -> PathBuf {
+        PathBuf::from(CACHE_DIR_NAME)
+    }
+
+    /// Return cached results for `path`/`contents`, or compute and store them.
+    ///
+    /// `analyze` is invoked only on a miss — a changed hash, a new file, or a
+    /// missing entry file.
+    pub fn get_or_analyze<F>(
+        &mut self,
+        path: &Path,
+        contents: &str,
+        analyze: F,
+    ) -> Result<Vec<AnalysisResult>>
+    where
+        F: FnOnce() -> Result<Vec<AnalysisResult>>,
+    {
+        let key = absolute_key(path);
+        let hash = hash_contents(contents);
+
+        if self.index.entries.get(&key) == Some(&hash) {
+            if let Some(results) = self.read_entry(hash) {
+                self.hits += 1;
+                return Ok(results);
+            }
+        }
+
+        let results = analyze()?;
+        self.misses += 1;
+        self.write_entry(hash, &results)?;
+        self.index.entries.insert(key, hash);
+        Ok(results)
+    }
+
+    /// Cache hit count for the current run.
+    pub fn hits(&self) -> u32 {
+        self.hits
+    }
+
+    /// Cache miss count for the current run.
+    pub fn misses(&self) -> u32 {
+        self.misses
+    }
+
+    /// Persist the index to disk.
+    pub fn save(&self) -> Result<()> {
+        let path = self.dir.join("index.json");
+        let json = serde_json::to_string(&self.index)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load the index, discarding it on a version mismatch or parse error.
+    fn load_index(dir: &Path) -> CacheIndex {
+        let fresh = || CacheIndex {
+            analyzer_version: ANALYZER_VERSION.to_string(),
+            entries: HashMap::new(),
+        };
+
+        let path = dir.join("index.json");
+        let Ok(bytes) = std::fs::read(&path) else {
+            return fresh();
+        };
+        match serde_json::from_slice::<CacheIndex>(&bytes) {
+            Ok(index) if index.analyzer_version == ANALYZER_VERSION => index,
+            Ok(_) => {
+                info!("Analyzer version changed; invalidating analysis cache");
+                fresh()
+            }
+            Err(_) => fresh(),
+        }
+    }
+
+    /// Path of the per-hash entry file.
+    fn entry_path(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{hash:016x}.json"))
+    }
+
+    /// Read a per-hash entry, if present and well-formed.
+    fn read_entry(&self, hash: u64) -> Option<Vec<AnalysisResult>> {
+        let bytes = std::fs::read(self.entry_path(hash)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Write a per-hash entry.
+    fn write_entry(&self, hash: u64, results: &[AnalysisResult]) -> Result<()> {
+        let json = serde_json::to_string(results)?;
+        std::fs::write(self.entry_path(hash), json)?;
+        Ok(())
+    }
+}
+
+/// Canonicalize `path` for use as a stable cache key, falling back to the
+/// lossy display form when the file cannot be resolved.
+fn absolute_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string_lossy().into_owned())
+}
+
+/// Deterministic content hash used as the cache key discriminator.
+fn hash_contents(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// SPDX-FileCopyrightText: 2025 Jonathan D.A. Jewell
+
+//! Language Server Protocol frontend for sustainabot.
+//!
+//! Republishes [`analyze_file`] output as `textDocument/publishDiagnostics`,
+//! giving editors the same live feedback loop rust-analyzer provides — but for
+//! energy and carbon cost instead of type errors.
+//!
+//! Diagnostics refresh on `didOpen`, `didChange`, and `didSave`. Because
+//! [`analyze_file`] is path-oriented, the unsaved buffer text delivered by
+//! `didOpen`/`didChange` is staged in a temporary file and analyzed there (see
+//! [`analyze_buffer`]), so feedback stays live mid-edit instead of reflecting
+//! stale on-disk content. `didSave` analyzes the file in place.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use lsp_server::{Connection, Message, Request as ServerRequest, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidOpenTextDocument, DidSaveTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionProviderCapability, Diagnostic,
+    DiagnosticRelatedInformation, DiagnosticSeverity, InitializeParams, Location, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url,
+};
+use sustainabot_metrics::AnalysisResult;
+use tracing::info;
+
+/// Default eco-score threshold below which a function is reported.
+const DEFAULT_ECO_THRESHOLD: f64 = 50.0;
+
+/// Run the LSP server over stdio until the client disconnects.
+pub fn run() -> Result<()> {
+    info!("Starting sustainabot LSP server (stdio)");
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    })?;
+
+    let init_params = connection.initialize(capabilities)?;
+    let eco_threshold = parse_threshold(&init_params);
+    info!("LSP initialized (eco threshold: {})", eco_threshold);
+
+    main_loop(&connection, eco_threshold)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Read the eco-score threshold from `initializationOptions.eco_threshold`.
+fn parse_threshold(params: &serde_json::Value) -> f64 {
+    serde_json::from_value::<InitializeParams>(params.clone())
+        .ok()
+        .and_then(|p| p.initialization_options)
+        .and_then(|opts| opts.get("eco_threshold").and_then(|v| v.as_f64()))
+        .unwrap_or(DEFAULT_ECO_THRESHOLD)
+}
+
+/// Dispatch incoming messages, re-analyzing on document lifecycle events.
+fn main_loop(connection: &Connection, eco_threshold: f64) -> Result<()> {
+    for msg in &connection.receiver {
+        match msg {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                if req.method == CodeActionRequest::METHOD {
+                    handle_code_action(connection, req, eco_threshold)?;
+                }
+            }
+            Message::Notification(note) => {
+                if let Some(refresh) = refresh_request(&note) {
+                    republish(connection, &refresh, eco_threshold)?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// A document refresh triggered by a lifecycle notification.
+struct Refresh {
+    /// The document's URI, used for the path to analyze and the published uri.
+    uri: Url,
+    /// Unsaved buffer contents from `didOpen`/`didChange`; `None` analyzes the
+    /// file on disk (`didSave`).
+    buffer: Option<String>,
+}
+
+/// Build a [`Refresh`] from a lifecycle notification, if it carries one.
+///
+/// `didOpen`/`didChange` carry the live buffer, so it is analyzed in place of
+/// the on-disk file; `didSave` has already flushed to disk. With
+/// [`TextDocumentSyncKind::FULL`] the last content change holds the whole
+/// document.
+fn refresh_request(note: &lsp_server::Notification) -> Option<Refresh> {
+    match note.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let p = serde_json::from_value::<lsp_types::DidOpenTextDocumentParams>(
+                note.params.clone(),
+            )
+            .ok()?;
+            Some(Refresh { uri: p.text_document.uri, buffer: Some(p.text_document.text) })
+        }
+        DidChangeTextDocument::METHOD => {
+            let p = serde_json::from_value::<lsp_types::DidChangeTextDocumentParams>(
+                note.params.clone(),
+            )
+            .ok()?;
+            let buffer = p.content_changes.into_iter().last().map(|c| c.text);
+            Some(Refresh { uri: p.text_document.uri, buffer })
+        }
+        DidSaveTextDocument::METHOD => {
+            let p = serde_json::from_value::<lsp_types::DidSaveTextDocumentParams>(
+                note.params.clone(),
+            )
+            .ok()?;
+            Some(Refresh { uri: p.text_document.uri, buffer: None })
+        }
+        _ => None,
+    }
+}
+
+/// Re-run analysis for a document and publish the resulting diagnostics.
+fn republish(connection: &Connection, refresh: &Refresh, eco_threshold: f64) -> Result<()> {
+    let Some(path) = file_path(&refresh.uri) else {
+        return Ok(());
+    };
+
+    let diagnostics = match analyze_path(&path, refresh.buffer.as_deref(), eco_threshold) {
+        Ok(diags) => diags,
+        Err(e) => {
+            info!("Analysis failed for {}: {}", path.display(), e);
+            Vec::new()
+        }
+    };
+
+    let params = PublishDiagnosticsParams {
+        uri: refresh.uri.clone(),
+        diagnostics,
+        version: None,
+    };
+    connection.sender.send(Message::Notification(lsp_server::Notification {
+        method: PublishDiagnostics::METHOD.to_string(),
+        params: serde_json::to_value(params)?,
+    }))?;
+    Ok(())
+}
+
+/// Analyze `path` — or `buffer` staged for it — and turn each finding into a
+/// diagnostic anchored to `path`'s URI.
+fn analyze_path(path: &Path, buffer: Option<&str>, eco_threshold: f64) -> Result<Vec<Diagnostic>> {
+    let uri = Url::from_file_path(path).ok();
+    let results = match buffer {
+        Some(text) => analyze_buffer(path, text)?,
+        None => sustainabot_analysis::analyze_file(path)?,
+    };
+    let mut diagnostics = Vec::new();
+
+    for result in &results {
+        if result.health.eco_score.0 < eco_threshold {
+            diagnostics.push(eco_diagnostic(result, uri.as_ref()));
+        }
+        for pattern in &result.patterns {
+            diagnostics.push(pattern_diagnostic(result, pattern));
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Analyze unsaved buffer text by staging it in a temporary file.
+///
+/// [`analyze_file`](sustainabot_analysis::analyze_file) is path-oriented and
+/// dispatches on the file extension, so the buffer is written to a file in the
+/// system temp directory carrying the original extension and analyzed there. The temp file is
+/// removed before returning, whether or not analysis succeeds.
+fn analyze_buffer(path: &Path, text: &str) -> Result<Vec<AnalysisResult>> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("buffer");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("rs");
+    let temp = std::env::temp_dir()
+        .join(format!("sustainabot-lsp-{}-{}.{}", std::process::id(), stem, ext));
+
+    std::fs::write(&temp, text)?;
+    let results = sustainabot_analysis::analyze_file(&temp);
+    let _ = std::fs::remove_file(&temp);
+    results
+}
+
+/// Build the eco-score diagnostic, folding recommendations into related info.
+fn eco_diagnostic(result: &AnalysisResult, uri: Option<&Url>) -> Diagnostic {
+    let score = result.health.eco_score.0;
+    let name = result.location.name.as_deref().unwrap_or("<anonymous>");
+    let range = location_range(result);
+
+    let related = match uri {
+        Some(uri) if !result.recommendations.is_empty() => Some(
+            result
+                .recommendations
+                .iter()
+                .map(|rec| DiagnosticRelatedInformation {
+                    location: Location::new(uri.clone(), range),
+                    message: rec.clone(),
+                })
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    Diagnostic {
+        range,
+        severity: Some(severity_from_eco_score(score)),
+        source: Some("sustainabot".to_string()),
+        message: format!("{} eco score {:.1}/100", name, score),
+        related_information: related,
+        ..Default::default()
+    }
+}
+
+/// Build a diagnostic for a detected pattern, keyed by its name.
+fn pattern_diagnostic(result: &AnalysisResult, pattern: &sustainabot_metrics::Pattern) -> Diagnostic {
+    Diagnostic {
+        range: location_range(result),
+        severity: Some(severity_from_pattern(pattern.severity)),
+        source: Some("sustainabot".to_string()),
+        code: Some(lsp_types::NumberOrString::String(pattern.kind.clone())),
+        message: format!("{}: {} ({})", pattern.kind, pattern.description, pattern.estimated_impact),
+        ..Default::default()
+    }
+}
+
+/// Surface high-energy recommendations as informational code actions.
+///
+/// Only functions overlapping `params.range` contribute actions, so the
+/// suggestions stay contextual to the cursor rather than flooding the menu with
+/// every below-threshold function in the file.
+fn handle_code_action(connection: &Connection, req: ServerRequest, eco_threshold: f64) -> Result<()> {
+    let params: lsp_types::CodeActionParams = serde_json::from_value(req.params)?;
+    let mut actions: Vec<CodeActionOrCommand> = Vec::new();
+
+    let uri = params.text_document.uri.clone();
+    if let Some(path) = file_path(&uri) {
+        if let Ok(results) = sustainabot_analysis::analyze_file(&path) {
+            for result in &results {
+                if result.health.eco_score.0 >= eco_threshold {
+                    continue;
+                }
+                if !ranges_intersect(location_range(result), params.range) {
+                    continue;
+                }
+                // Link the recommendation back to the eco diagnostic so the
+                // client can relate it to what it already renders in the gutter.
+                let diagnostic = eco_diagnostic(result, Some(&uri));
+                for rec in &result.recommendations {
+                    actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                        title: rec.clone(),
+                        kind: Some(quickinfo_kind()),
+                        diagnostics: Some(vec![diagnostic.clone()]),
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+    }
+
+    let response = Response::new_ok(req.id, actions);
+    connection.sender.send(Message::Response(response))?;
+    Ok(())
+}
+
+/// Code-action kind for sustainabot's informational eco recommendations.
+///
+/// Deliberately *not* [`CodeActionKind::QUICKFIX`]: these actions carry no
+/// workspace edit, so advertising them as quick fixes would leave editors
+/// offering an inert "fix". A dedicated kind keeps them out of the quick-fix
+/// menu while still surfacing the recommendation text.
+fn quickinfo_kind() -> CodeActionKind {
+    CodeActionKind::new("quickinfo.sustainabot")
+}
+
+/// Whether two ranges overlap (inclusive of shared endpoints).
+///
+/// Used to keep code actions contextual: a zero-width function location still
+/// "intersects" a selection that contains or abuts it.
+fn ranges_intersect(a: Range, b: Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+/// Convert a file URI into a local path.
+fn file_path(uri: &Url) -> Option<PathBuf> {
+    uri.to_file_path().ok()
+}
+
+/// Build a zero-width range at the function's reported location.
+///
+/// sustainabot locations are 1-based; LSP positions are 0-based.
+fn location_range(result: &AnalysisResult) -> Range {
+    let line = result.location.line.saturating_sub(1) as u32;
+    let col = result.location.column.saturating_sub(1) as u32;
+    let pos = Position::new(line, col);
+    Range::new(pos, pos)
+}
+
+/// Map an eco score to an LSP diagnostic severity.
+fn severity_from_eco_score(score: f64) -> DiagnosticSeverity {
+    if score < 40.0 {
+        DiagnosticSeverity::ERROR
+    } else if score < 70.0 {
+        DiagnosticSeverity::WARNING
+    } else {
+        DiagnosticSeverity::INFORMATION
+    }
+}
+
+/// Map a pattern's severity to an LSP diagnostic severity.
+fn severity_from_pattern(severity: sustainabot_metrics::PatternSeverity) -> DiagnosticSeverity {
+    use sustainabot_metrics::PatternSeverity::*;
+    match severity {
+        High => DiagnosticSeverity::ERROR,
+        Medium => DiagnosticSeverity::WARNING,
+        Low => DiagnosticSeverity::INFORMATION,
+        Info => DiagnosticSeverity::HINT,
+    }
+}
@@ -13,6 +13,10 @@ use sustainabot_analysis::analyze_file;
 use tracing::info;
 use walkdir::WalkDir;
 
+mod cache;
+mod lsp;
+mod sarif;
+
 #[derive(Parser)]
 #[command(name = "sustainabot")]
 #[command(about = "Ecological & Economic Code Analysis", long_about = None)]
@@ -46,10 +50,28 @@ enum Commands {
         /// Minimum eco score threshold (0-100)
         #[arg(long, default_value = "50")]
         eco_threshold: f64,
+
+        /// Emit GitHub Actions workflow-command annotations
+        ///
+        /// Auto-enabled when the `GITHUB_ACTIONS` environment variable is
+        /// `true`, so it lights up inside CI without extra wiring.
+        #[arg(long)]
+        annotations: bool,
+
+        /// Skip the incremental analysis cache for this run
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Delete the analysis cache before running
+        #[arg(long)]
+        clean_cache: bool,
     },
 
     /// Show analysis of sustainabot itself (dogfooding!)
     SelfAnalyze,
+
+    /// Run a Language Server Protocol server over stdio
+    Lsp,
 }
 
 fn main() -> Result<()> {
@@ -74,16 +96,32 @@ fn main() -> Result<()> {
                 "text" => {
                     print_results_text(&results);
                 }
+                "sarif" => {
+                    let log = sarif::to_sarif(&results, &file);
+                    println!("{}", serde_json::to_string_pretty(&log)?);
+                }
                 _ => {
                     eprintln!("Unsupported format: {}", format);
                 }
             }
         }
 
-        Commands::Check { path, eco_threshold } => {
+        Commands::Check { path, eco_threshold, annotations, no_cache, clean_cache } => {
             info!("Checking directory: {}", path.display());
             println!("Checking directory: {} (eco threshold: {})\n", path.display(), eco_threshold);
 
+            let annotations = annotations
+                || std::env::var("GITHUB_ACTIONS").map(|v| v == "true").unwrap_or(false);
+
+            if clean_cache {
+                cache::AnalysisCache::clean(&cache::AnalysisCache::dir())?;
+            }
+            let mut analysis_cache = if no_cache {
+                None
+            } else {
+                Some(cache::AnalysisCache::open()?)
+            };
+
             let mut total_files = 0u32;
             let mut files_below_threshold = 0u32;
             let mut all_results = Vec::new();
@@ -108,7 +146,18 @@ fn main() -> Result<()> {
                     continue;
                 }
 
-                match analyze_file(entry_path) {
+                let analyzed = match &mut analysis_cache {
+                    Some(cache) => match std::fs::read_to_string(entry_path) {
+                        Ok(contents) => {
+                            cache.get_or_analyze(entry_path, &contents, || analyze_file(entry_path))
+                        }
+                        // Non-UTF-8 or unreadable files bypass the cache.
+                        Err(_) => analyze_file(entry_path),
+                    },
+                    None => analyze_file(entry_path),
+                };
+
+                match analyzed {
                     Ok(results) => {
                         total_files += 1;
                         for result in &results {
@@ -121,6 +170,9 @@ fn main() -> Result<()> {
                                     result.health.eco_score.0,
                                     eco_threshold
                                 );
+                                if annotations {
+                                    print_annotation(entry_path, result, eco_threshold);
+                                }
                             }
                         }
                         all_results.extend(results);
@@ -131,11 +183,18 @@ fn main() -> Result<()> {
                 }
             }
 
+            if let Some(cache) = &analysis_cache {
+                cache.save()?;
+            }
+
             // Summary
             println!("\n--- Summary ---");
             println!("Files analyzed:        {}", total_files);
             println!("Functions found:       {}", all_results.len());
             println!("Below threshold:       {}", files_below_threshold);
+            if let Some(cache) = &analysis_cache {
+                println!("Cache hits/misses:     {}/{}", cache.hits(), cache.misses());
+            }
 
             if !all_results.is_empty() {
                 let avg_eco: f64 = all_results.iter().map(|r| r.health.eco_score.0).sum::<f64>()
@@ -152,9 +211,21 @@ fn main() -> Result<()> {
             }
 
             if files_below_threshold > 0 {
+                if annotations {
+                    println!(
+                        "::error::{} function(s) below eco threshold {}",
+                        files_below_threshold, eco_threshold
+                    );
+                }
                 println!("\nResult: FAIL ({} functions below eco threshold {})", files_below_threshold, eco_threshold);
                 std::process::exit(1);
             } else {
+                if annotations {
+                    println!(
+                        "::notice::all {} function(s) meet eco threshold {}",
+                        all_results.len(), eco_threshold
+                    );
+                }
                 println!("\nResult: PASS (all functions meet eco threshold {})", eco_threshold);
             }
         }
@@ -177,11 +248,43 @@ fn main() -> Result<()> {
                 println!("Run from sustainabot repository root.");
             }
         }
+
+        Commands::Lsp => {
+            lsp::run()?;
+        }
     }
 
     Ok(())
 }
 
+/// Emit a GitHub Actions workflow-command annotation for a below-threshold
+/// function. The severity word escalates with the size of the shortfall.
+fn print_annotation(
+    file: &std::path::Path,
+    result: &sustainabot_metrics::AnalysisResult,
+    eco_threshold: f64,
+) {
+    let score = result.health.eco_score.0;
+    let gap = eco_threshold - score;
+    let level = if gap >= eco_threshold * 0.5 {
+        "error"
+    } else if gap >= eco_threshold * 0.2 {
+        "warning"
+    } else {
+        "notice"
+    };
+    println!(
+        "::{level} file={path},line={line},col={col}::{function} eco score {score:.1} below threshold {eco_threshold}",
+        level = level,
+        path = file.display(),
+        line = result.location.line,
+        col = result.location.column,
+        function = result.location.name.as_deref().unwrap_or("<anon>"),
+        score = score,
+        eco_threshold = eco_threshold,
+    );
+}
+
 fn print_results_text(results: &[sustainabot_metrics::AnalysisResult]) {
     for result in results {
         println!("\n📍 Function: {}", result.location.name.as_deref().unwrap_or("<anonymous>"));
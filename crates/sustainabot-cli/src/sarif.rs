@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// SPDX-FileCopyrightText: 2025 Jonathan D.A. Jewell
+
+//! SARIF 2.1.0 serialization for sustainabot findings.
+//!
+//! Emits the Static Analysis Results Interchange Format so ecological
+//! findings load into any code-scanning dashboard the same way clippy or
+//! rustfmt output does.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use sustainabot_metrics::AnalysisResult;
+
+/// The version string reported in the SARIF `tool.driver`.
+const DRIVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Serialize analysis results for a single file into a SARIF 2.1.0 log.
+///
+/// The driver declares one rule per eco-threshold check and one rule per
+/// detected pattern kind; each finding becomes a `results[]` entry anchored
+/// to its `location` in `file`.
+pub fn to_sarif(results: &[AnalysisResult], file: &Path) -> Value {
+    let uri = file.to_string_lossy().to_string();
+
+    let mut rules: Vec<Value> = vec![eco_score_rule()];
+    let mut seen_patterns = Vec::new();
+    let mut findings: Vec<Value> = Vec::new();
+
+    for result in results {
+        findings.push(eco_score_result(result, &uri));
+
+        for pattern in &result.patterns {
+            if !seen_patterns.contains(&pattern.kind) {
+                seen_patterns.push(pattern.kind.clone());
+                rules.push(pattern_rule(&pattern.kind, &pattern.description));
+            }
+            findings.push(pattern_result(result, pattern, &uri));
+        }
+    }
+
+    json!({
+        "version": "2.1.0",
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "sustainabot",
+                    "informationUri": "https://github.com/hyperpolymath/sustainabot",
+                    "version": DRIVER_VERSION,
+                    "rules": rules,
+                }
+            },
+            "results": findings,
+        }],
+    })
+}
+
+/// Declares the eco-score threshold check as a reusable rule.
+fn eco_score_rule() -> Value {
+    json!({
+        "id": "sustainabot/eco-score",
+        "name": "EcoScore",
+        "shortDescription": { "text": "Function ecological health score" },
+        "fullDescription": {
+            "text": "Estimated energy and carbon cost of a function, scored 0-100 where lower is more wasteful."
+        },
+        "defaultConfiguration": { "level": "warning" },
+    })
+}
+
+/// Declares a rule for a detected pattern kind.
+fn pattern_rule(kind: &str, description: &str) -> Value {
+    json!({
+        "id": format!("sustainabot/pattern/{}", kind),
+        "name": kind,
+        "shortDescription": { "text": format!("Ecological pattern: {}", kind) },
+        "fullDescription": { "text": description },
+        "defaultConfiguration": { "level": "note" },
+    })
+}
+
+/// Builds the eco-score `results[]` entry for a function.
+fn eco_score_result(result: &AnalysisResult, uri: &str) -> Value {
+    let score = result.health.eco_score.0;
+    let name = result.location.name.as_deref().unwrap_or("<anonymous>");
+    let text = format!(
+        "{} has eco score {:.1}/100 ({:.2} J, {:.4} gCO2e)",
+        name, score, result.resources.energy.0, result.resources.carbon.0
+    );
+    finding("sustainabot/eco-score", level_from_eco_score(score), &text, result, uri)
+}
+
+/// Builds a `results[]` entry for a detected pattern.
+fn pattern_result(
+    result: &AnalysisResult,
+    pattern: &sustainabot_metrics::Pattern,
+    uri: &str,
+) -> Value {
+    let name = result.location.name.as_deref().unwrap_or("<anonymous>");
+    let text = format!(
+        "{} in {}: {}. Estimated impact: {}",
+        pattern.kind, name, pattern.description, pattern.estimated_impact
+    );
+    finding(
+        &format!("sustainabot/pattern/{}", pattern.kind),
+        level_from_pattern_severity(pattern.severity),
+        &text,
+        result,
+        uri,
+    )
+}
+
+/// Assembles a SARIF result with a physical location.
+fn finding(rule_id: &str, level: &str, text: &str, result: &AnalysisResult, uri: &str) -> Value {
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": text },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": {
+                    "startLine": result.location.line,
+                    "startColumn": result.location.column,
+                }
+            }
+        }],
+    })
+}
+
+/// Maps an eco score to a SARIF level. Lower scores are more wasteful.
+fn level_from_eco_score(score: f64) -> &'static str {
+    if score < 40.0 {
+        "error"
+    } else if score < 70.0 {
+        "warning"
+    } else {
+        "note"
+    }
+}
+
+/// Maps a pattern's severity to a SARIF level.
+fn level_from_pattern_severity(severity: sustainabot_metrics::PatternSeverity) -> &'static str {
+    use sustainabot_metrics::PatternSeverity::*;
+    match severity {
+        High => "error",
+        Medium => "warning",
+        Low | Info => "note",
+    }
+}
@@ -6,6 +6,8 @@
 //! Publishes ecological and economic analysis findings to the shared context
 //! layer for consumption by other bots in the fleet.
 
+use std::collections::HashMap;
+
 use anyhow::Result;
 use gitbot_shared_context::{BotId, Context, Finding, Severity};
 use sustainabot_metrics::{Carbon, Duration, Energy, Memory};
@@ -18,6 +20,9 @@ pub struct AnalysisResult {
     pub carbon: Carbon,
     pub duration: Duration,
     pub patterns: Vec<Pattern>,
+    /// Names of functions this one calls, used to build the call graph for
+    /// cumulative energy attribution.
+    pub callees: Vec<String>,
 }
 
 /// Detected code pattern with ecological impact
@@ -123,6 +128,39 @@ pub fn publish_findings(
         ));
     }
 
+    // Cumulative call-graph attribution: a cheap wrapper calling an expensive
+    // helper is invisible to the per-function check above, so rank functions by
+    // the energy of everything they transitively reach.
+    let cumulative = cumulative_energy(results);
+    let max_cumulative = cumulative
+        .values()
+        .copied()
+        .fold(0.0_f64, f64::max);
+
+    if max_cumulative > 0.0 {
+        let mut hot_paths: Vec<(&AnalysisResult, f64)> = results
+            .iter()
+            .filter_map(|r| cumulative.get(&r.function_name).map(|&c| (r, c)))
+            // A hot path dominates the run and carries more than its own weight
+            // (i.e. the cost lives in its callees).
+            .filter(|(r, c)| *c >= 0.5 * max_cumulative && *c > r.energy.0)
+            .collect();
+        hot_paths.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (result, cumulative_energy) in hot_paths {
+            let finding_id = format!("SUSTAIN-HOT-PATH-{}", result.function_name);
+            ctx.add_finding(Finding::new(
+                BotId::Sustainabot,
+                &finding_id,
+                Severity::Warning,
+                &format!(
+                    "Hot path {}: cumulative energy {:.2}J (own {:.2}J) dominates the run",
+                    result.function_name, cumulative_energy, result.energy.0
+                ),
+            ));
+        }
+    }
+
     // Add ecological efficiency rating
     let efficiency_rating = calculate_efficiency_rating(results, thresholds);
     ctx.add_finding(Finding::new(
@@ -132,6 +170,39 @@ pub fn publish_findings(
         &format!("Ecological efficiency rating: {}", efficiency_rating),
     ));
 
+    // Grade each function relative to the run's distribution so authors can see
+    // which functions pull the rating down, not just the aggregate letter.
+    for (result, grade) in results.iter().zip(function_grades(results, thresholds)) {
+        let finding_id = format!("SUSTAIN-EFFICIENCY-GRADE-{}", result.function_name);
+        ctx.add_finding(Finding::new(
+            BotId::Sustainabot,
+            &finding_id,
+            Severity::Info,
+            &format!(
+                "{} efficiency grade: {} ({}) at {:.2}J",
+                result.function_name,
+                grade,
+                grade_label(grade),
+                result.energy.0
+            ),
+        ));
+    }
+
+    // Summarize the energy distribution so users can see the spread the grades
+    // were computed against.
+    if !results.is_empty() {
+        let p = energy_percentiles(results);
+        ctx.add_finding(Finding::new(
+            BotId::Sustainabot,
+            "SUSTAIN-EFFICIENCY-DISTRIBUTION",
+            Severity::Info,
+            &format!(
+                "Energy distribution (J): p20={:.2}, p40={:.2}, p60={:.2}, p80={:.2}",
+                p[0], p[1], p[2], p[3]
+            ),
+        ));
+    }
+
     Ok(())
 }
 
@@ -155,31 +226,300 @@ impl Default for EcologicalThresholds {
     }
 }
 
-/// Calculate efficiency rating (A-F scale like energy ratings)
+/// Calculate the run's efficiency rating (A-F scale like energy ratings).
+///
+/// Individual functions are graded *relative* to the run via [`function_grades`]
+/// (percentile bands), but the run as a whole is graded *absolutely*: by the
+/// share of functions that breach the per-function energy floor in
+/// [`EcologicalThresholds`]. The relative bands only say where a function sits
+/// within this run — only the floor says whether the run is wasteful in
+/// absolute terms. Grading on the floor share means a uniformly-efficient tree
+/// earns an `A` and a uniformly-wasteful one an `F`, regardless of how the
+/// run's own distribution happens to be skewed.
 fn calculate_efficiency_rating(
     results: &[AnalysisResult],
-    _thresholds: &EcologicalThresholds,
+    thresholds: &EcologicalThresholds,
 ) -> String {
     if results.is_empty() {
         return "N/A".to_string();
     }
 
-    let avg_energy: f64 = results.iter().map(|r| r.energy.0).sum::<f64>() / results.len() as f64;
+    let over_floor = results
+        .iter()
+        .filter(|r| r.energy.0 > thresholds.energy_per_function_joules)
+        .count();
+    let grade = grade_run(over_floor, results.len());
+    format!("{} ({})", grade, grade_label(grade))
+}
 
-    // Simple rating scale (could be more sophisticated)
-    if avg_energy < 10.0 {
-        "A (Excellent)".to_string()
-    } else if avg_energy < 50.0 {
-        "B (Good)".to_string()
-    } else if avg_energy < 100.0 {
-        "C (Average)".to_string()
-    } else if avg_energy < 200.0 {
-        "D (Below Average)".to_string()
-    } else if avg_energy < 500.0 {
-        "E (Poor)".to_string()
+/// Grade the run from the share of functions breaching the absolute floor.
+fn grade_run(over_floor: usize, total: usize) -> char {
+    if over_floor == 0 {
+        return 'A';
+    }
+    let share = over_floor as f64 / total as f64;
+    if share <= 0.10 {
+        'B'
+    } else if share <= 0.25 {
+        'C'
+    } else if share <= 0.50 {
+        'D'
+    } else if share <= 0.75 {
+        'E'
     } else {
-        "F (Very Poor)".to_string()
+        'F'
+    }
+}
+
+/// Grade each function against the run's energy distribution.
+///
+/// Returns a grade per `results` entry, in order: [`grade_energy`] on each
+/// function's own energy against the run's percentile breakpoints. A function
+/// over the absolute per-function floor is graded `F` regardless of its band.
+fn function_grades(results: &[AnalysisResult], thresholds: &EcologicalThresholds) -> Vec<char> {
+    if results.is_empty() {
+        return Vec::new();
     }
+    let breakpoints = energy_percentiles(results);
+    results
+        .iter()
+        .map(|r| grade_energy(r.energy.0, &breakpoints, thresholds))
+        .collect()
+}
+
+/// The ≤20th/≤40th/≤60th/≤80th percentile energy breakpoints over `results`.
+fn energy_percentiles(results: &[AnalysisResult]) -> [f64; 4] {
+    let mut energies: Vec<f64> = results.iter().map(|r| r.energy.0).collect();
+    energies.sort_by(f64::total_cmp);
+    [
+        percentile(&energies, 0.20),
+        percentile(&energies, 0.40),
+        percentile(&energies, 0.60),
+        percentile(&energies, 0.80),
+    ]
+}
+
+/// Linear-interpolated percentile estimate over a sorted, non-empty slice.
+///
+/// `p` is a fraction in `[0, 1]`; interpolation is between the two closest
+/// ranks, matching the common "type 7" quantile definition.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    debug_assert!(!sorted.is_empty());
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let frac = rank - lower as f64;
+    sorted[lower] + frac * (sorted[upper] - sorted[lower])
+}
+
+/// Grade a single energy value against percentile breakpoints, applying the
+/// absolute per-function threshold as a floor.
+fn grade_energy(energy: f64, breakpoints: &[f64; 4], thresholds: &EcologicalThresholds) -> char {
+    if energy > thresholds.energy_per_function_joules {
+        return 'F';
+    }
+    if energy <= breakpoints[0] {
+        'A'
+    } else if energy <= breakpoints[1] {
+        'B'
+    } else if energy <= breakpoints[2] {
+        'C'
+    } else if energy <= breakpoints[3] {
+        'D'
+    } else {
+        'E'
+    }
+}
+
+/// Human-readable label for a grade letter.
+fn grade_label(grade: char) -> &'static str {
+    match grade {
+        'A' => "Excellent",
+        'B' => "Good",
+        'C' => "Average",
+        'D' => "Below Average",
+        'E' => "Poor",
+        _ => "Very Poor",
+    }
+}
+
+/// Compute each function's cumulative energy: its own energy plus the energy
+/// of everything it transitively calls, each reached function counted once.
+///
+/// The call graph is condensed with Tarjan's algorithm so recursive and
+/// mutually-recursive cycles collapse into a single node carrying the whole
+/// cycle's own-energy. Edges to unresolved callees are dropped, capping
+/// propagation at the analyzed boundary. Shared callees are de-duplicated via a
+/// reachable-set union rather than summed, avoiding double-counting.
+fn cumulative_energy(results: &[AnalysisResult]) -> HashMap<String, f64> {
+    let n = results.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    // Resolve function names to node indices (caller -> callee edges).
+    let index_of: HashMap<&str, usize> = results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.function_name.as_str(), i))
+        .collect();
+    let adjacency: Vec<Vec<usize>> = results
+        .iter()
+        .map(|r| {
+            r.callees
+                .iter()
+                .filter_map(|callee| index_of.get(callee.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let sccs = tarjan_scc(&adjacency);
+
+    // Map each node to its SCC id and accumulate per-SCC own-energy.
+    let mut scc_of = vec![0usize; n];
+    let mut scc_energy = vec![0.0_f64; sccs.len()];
+    for (scc_id, members) in sccs.iter().enumerate() {
+        for &node in members {
+            scc_of[node] = scc_id;
+            scc_energy[scc_id] += results[node].energy.0;
+        }
+    }
+
+    // Condense edges between distinct SCCs.
+    let mut scc_adjacency: Vec<Vec<usize>> = vec![Vec::new(); sccs.len()];
+    for (node, succ) in adjacency.iter().enumerate() {
+        let from = scc_of[node];
+        for &to_node in succ {
+            let to = scc_of[to_node];
+            if to != from && !scc_adjacency[from].contains(&to) {
+                scc_adjacency[from].push(to);
+            }
+        }
+    }
+
+    // Reachable-set sum over the condensation DAG (memoized), counting each
+    // reachable SCC exactly once.
+    let mut memo: Vec<Option<f64>> = vec![None; sccs.len()];
+    for scc_id in 0..sccs.len() {
+        reachable_energy(scc_id, &scc_adjacency, &scc_energy, &mut memo, &mut Vec::new());
+    }
+
+    results
+        .iter()
+        .map(|r| {
+            let energy = memo[scc_of[index_of[r.function_name.as_str()]]].unwrap_or(0.0);
+            (r.function_name.clone(), energy)
+        })
+        .collect()
+}
+
+/// Cumulative own-energy of `scc` and every SCC reachable from it, memoized.
+fn reachable_energy(
+    scc: usize,
+    adjacency: &[Vec<usize>],
+    scc_energy: &[f64],
+    memo: &mut [Option<f64>],
+    stack: &mut Vec<usize>,
+) -> f64 {
+    if let Some(value) = memo[scc] {
+        return value;
+    }
+
+    // Collect the set of reachable SCCs (including self) so shared descendants
+    // are counted once, then sum their own-energy.
+    let mut reachable = std::collections::HashSet::new();
+    collect_reachable(scc, adjacency, &mut reachable, stack);
+    let total: f64 = reachable.iter().map(|&id| scc_energy[id]).sum();
+    memo[scc] = Some(total);
+    total
+}
+
+/// Depth-first collection of SCCs reachable from `scc` (the condensation is a
+/// DAG, so the on-path `stack` guard is belt-and-braces).
+fn collect_reachable(
+    scc: usize,
+    adjacency: &[Vec<usize>],
+    reachable: &mut std::collections::HashSet<usize>,
+    stack: &mut Vec<usize>,
+) {
+    if !reachable.insert(scc) || stack.contains(&scc) {
+        return;
+    }
+    stack.push(scc);
+    for &next in &adjacency[scc] {
+        collect_reachable(next, adjacency, reachable, stack);
+    }
+    stack.pop();
+}
+
+/// Tarjan's strongly-connected-components algorithm over an adjacency list.
+/// Returns one vector of node indices per SCC.
+fn tarjan_scc(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        adjacency: &'a [Vec<usize>],
+        index: usize,
+        indices: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        sccs: Vec<Vec<usize>>,
+    }
+
+    fn strong_connect(state: &mut State, v: usize) {
+        state.indices[v] = Some(state.index);
+        state.lowlink[v] = state.index;
+        state.index += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        for &w in &state.adjacency[v] {
+            match state.indices[w] {
+                None => {
+                    strong_connect(state, w);
+                    state.lowlink[v] = state.lowlink[v].min(state.lowlink[w]);
+                }
+                Some(w_index) if state.on_stack[w] => {
+                    state.lowlink[v] = state.lowlink[v].min(w_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if state.lowlink[v] == state.indices[v].unwrap() {
+            let mut component = Vec::new();
+            loop {
+                let w = state.stack.pop().unwrap();
+                state.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            state.sccs.push(component);
+        }
+    }
+
+    let n = adjacency.len();
+    let mut state = State {
+        adjacency,
+        index: 0,
+        indices: vec![None; n],
+        lowlink: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        sccs: Vec::new(),
+    };
+
+    for v in 0..n {
+        if state.indices[v].is_none() {
+            strong_connect(&mut state, v);
+        }
+    }
+
+    state.sccs
 }
 
 /// Map sustainabot PatternSeverity to fleet Severity
@@ -208,9 +548,78 @@ mod tests {
             carbon: Carbon::grams_co2e(0.1),
             duration: Duration::milliseconds(10.0),
             patterns: vec![],
+            callees: vec![],
         }];
 
         let rating = calculate_efficiency_rating(&results, &thresholds);
         assert!(rating.starts_with('A'));
     }
+
+    #[test]
+    fn test_run_grade_tracks_floor_breaches_not_distribution() {
+        let thresholds = EcologicalThresholds::default();
+
+        // A uniformly-efficient tree (nothing over the floor) earns an A even
+        // though its own distribution still spans the relative bands.
+        let efficient: Vec<AnalysisResult> =
+            [1.0, 2.0, 3.0, 4.0, 5.0].iter().map(|&e| result("fn", e, &[])).collect();
+        assert!(calculate_efficiency_rating(&efficient, &thresholds).starts_with('A'));
+
+        // A uniformly-wasteful tree (everything over the floor) earns an F.
+        let wasteful: Vec<AnalysisResult> =
+            [200.0, 300.0, 400.0].iter().map(|&e| result("fn", e, &[])).collect();
+        assert!(calculate_efficiency_rating(&wasteful, &thresholds).starts_with('F'));
+    }
+
+    #[test]
+    fn test_function_grades_one_per_result() {
+        let thresholds = EcologicalThresholds::default();
+        let results: Vec<AnalysisResult> =
+            [1.0, 2.0, 3.0, 4.0, 5.0].iter().map(|&e| result("fn", e, &[])).collect();
+
+        let grades = function_grades(&results, &thresholds);
+        assert_eq!(grades.len(), results.len());
+        // The cheapest function sits in the best relative band.
+        assert_eq!(grades[0], 'A');
+    }
+
+    fn result(name: &str, energy: f64, callees: &[&str]) -> AnalysisResult {
+        AnalysisResult {
+            function_name: name.to_string(),
+            file_path: "test.rs".to_string(),
+            energy: Energy::joules(energy),
+            carbon: Carbon::grams_co2e(0.0),
+            duration: Duration::milliseconds(0.0),
+            patterns: vec![],
+            callees: callees.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_cumulative_energy_shared_callee_not_double_counted() {
+        // wrapper -> {left, right} -> shared; `shared` is counted once.
+        let results = vec![
+            result("wrapper", 1.0, &["left", "right"]),
+            result("left", 2.0, &["shared"]),
+            result("right", 3.0, &["shared"]),
+            result("shared", 10.0, &[]),
+        ];
+
+        let cumulative = cumulative_energy(&results);
+        assert_eq!(cumulative["wrapper"], 1.0 + 2.0 + 3.0 + 10.0);
+        assert_eq!(cumulative["shared"], 10.0);
+    }
+
+    #[test]
+    fn test_cumulative_energy_collapses_cycles() {
+        // a <-> b form an SCC; cumulative energy is the combined own-energy.
+        let results = vec![
+            result("a", 4.0, &["b"]),
+            result("b", 6.0, &["a"]),
+        ];
+
+        let cumulative = cumulative_energy(&results);
+        assert_eq!(cumulative["a"], 10.0);
+        assert_eq!(cumulative["b"], 10.0);
+    }
 }